@@ -1,36 +1,98 @@
+use std::cell::{Cell, UnsafeCell};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::ptr;
 use std::sync::{
-    atomic::{AtomicUsize, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     Arc, Mutex,
 };
+use std::task::{Context, Poll, Wake, Waker};
 
 use aligned::{Aligned, A64};
 
-trait Queue {
+trait Queue<T> {
     fn new(capacity: usize) -> Self;
-    fn push(&mut self, val: i32) -> bool;
-    fn pop(&mut self, val: &mut i32) -> bool;
+    fn push(&self, val: T) -> Result<(), T>;
+    fn pop(&self) -> Option<T>;
 }
 
-#[derive(Debug, Default)]
-struct RingBuffer {
-    data_: Vec<i32>,
+/// A single-slot cell for a waker to be registered by one task and woken by
+/// another, mirroring the role of `futures`' `AtomicWaker`. `wake` runs on
+/// every `RingBuffer::push`/`pop`, including the purely synchronous path
+/// that never registers a waker at all, so it must not pay for the `Mutex`
+/// in that common case: `has_waker` is checked first with a plain atomic
+/// load, and the lock is only taken once a waker is actually known to be
+/// registered.
+struct WakerCell {
+    has_waker: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl WakerCell {
+    fn new() -> Self {
+        Self {
+            has_waker: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        }
+    }
+
+    fn register(&self, cx_waker: &Waker) {
+        let mut slot = self.waker.lock().unwrap();
+        if !slot.as_ref().is_some_and(|w| w.will_wake(cx_waker)) {
+            *slot = Some(cx_waker.clone());
+        }
+        self.has_waker.store(true, Ordering::Release);
+    }
+
+    fn wake(&self) {
+        if !self.has_waker.load(Ordering::Acquire) {
+            return;
+        }
+
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            self.has_waker.store(false, Ordering::Release);
+            waker.wake();
+        }
+    }
+}
+
+struct RingBuffer<T> {
+    data_: Box<[UnsafeCell<MaybeUninit<T>>]>,
     read_idx_: Aligned<A64, AtomicUsize>,
-    read_idx_cached_: usize,
+    read_idx_cached_: Cell<usize>,
     write_idx_: Aligned<A64, AtomicUsize>,
-    write_idx_cached_: usize,
+    write_idx_cached_: Cell<usize>,
+    consumer_waker_: WakerCell,
+    producer_waker_: WakerCell,
 }
-impl Queue for RingBuffer {
+
+unsafe impl<T: Send> Send for RingBuffer<T> {}
+unsafe impl<T: Send> Sync for RingBuffer<T> {}
+
+impl<T> Queue<T> for RingBuffer<T> {
     fn new(capacity: usize) -> Self {
+        let data_ = (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+
         Self {
-            data_: vec![0; capacity],
+            data_,
             read_idx_: Aligned(AtomicUsize::new(0)),
-            read_idx_cached_: 0,
+            read_idx_cached_: Cell::new(0),
             write_idx_: Aligned(AtomicUsize::new(0)),
-            write_idx_cached_: 0,
+            write_idx_cached_: Cell::new(0),
+            consumer_waker_: WakerCell::new(),
+            producer_waker_: WakerCell::new(),
         }
     }
 
-    fn push(&mut self, val: i32) -> bool {
+    // SAFETY: `push` only ever touches `read_idx_cached_`, which only the
+    // producer side reads or writes, so the `Cell` needs no synchronization
+    // with `pop` running concurrently on the consumer side.
+    fn push(&self, val: T) -> Result<(), T> {
         let write_idx = self.write_idx_.load(Ordering::Relaxed);
         let mut next_write_idx = write_idx + 1;
 
@@ -38,36 +100,769 @@ impl Queue for RingBuffer {
             next_write_idx = 0;
         }
 
-        if next_write_idx == self.read_idx_cached_ {
-            self.read_idx_cached_ = self.read_idx_.load(Ordering::Acquire);
+        if next_write_idx == self.read_idx_cached_.get() {
+            self.read_idx_cached_
+                .set(self.read_idx_.load(Ordering::Acquire));
 
-            if next_write_idx == self.read_idx_cached_ {
-                return false;
+            if next_write_idx == self.read_idx_cached_.get() {
+                return Err(val);
             }
         }
 
-        self.data_[write_idx] = val;
+        // SAFETY: write_idx is not yet visible to the consumer (it trails
+        // read_idx_cached_), so we have exclusive access to this slot.
+        unsafe {
+            (*self.data_[write_idx].get()).write(val);
+        }
         self.write_idx_.store(next_write_idx, Ordering::Relaxed);
+        self.consumer_waker_.wake();
 
-        true
+        Ok(())
     }
 
-    fn pop(&mut self, val: &mut i32) -> bool {
+    // SAFETY: `pop` only ever touches `write_idx_cached_`, which only the
+    // consumer side reads or writes, so the `Cell` needs no synchronization
+    // with `push` running concurrently on the producer side.
+    fn pop(&self) -> Option<T> {
         let read_idx = self.read_idx_.load(Ordering::Relaxed);
-        if read_idx == self.write_idx_cached_ {
-            self.write_idx_cached_ = self.write_idx_.load(Ordering::Acquire);
-            if read_idx == self.write_idx_cached_ {
-                return false;
+        if read_idx == self.write_idx_cached_.get() {
+            self.write_idx_cached_
+                .set(self.write_idx_.load(Ordering::Acquire));
+            if read_idx == self.write_idx_cached_.get() {
+                return None;
             }
         }
-        *val = self.data_[read_idx];
-        let mut next_read_idx = read_idx + 1;
 
+        // SAFETY: read_idx is behind write_idx_cached_, so the producer has
+        // already initialized this slot and will not touch it again until we
+        // advance read_idx_.
+        let val = unsafe { (*self.data_[read_idx].get()).assume_init_read() };
+
+        let mut next_read_idx = read_idx + 1;
         if next_read_idx == self.data_.len() {
             next_read_idx = 0;
         }
         self.read_idx_.store(next_read_idx, Ordering::Release);
-        true
+        self.producer_waker_.wake();
+
+        Some(val)
+    }
+}
+
+impl<T> RingBuffer<T> {
+    /// Splits the buffer into a single-producer handle and a single-consumer
+    /// handle that share ownership of the underlying storage. Each handle's
+    /// `push`/`pop` takes `&self`, so the two can run concurrently on
+    /// separate threads with no lock.
+    fn split(self) -> (Producer<T>, Consumer<T>) {
+        let inner = Arc::new(self);
+        (
+            Producer {
+                inner: Arc::clone(&inner),
+            },
+            Consumer { inner },
+        )
+    }
+}
+
+impl<T: Copy> RingBuffer<T> {
+    /// Free slots available to the producer, refreshing the cached read
+    /// index from the shared atomic only once the cache looks exhausted.
+    fn free_len(&self) -> usize {
+        let write_idx = self.write_idx_.load(Ordering::Relaxed);
+        let len = self.data_.len();
+
+        let free_from = |read_idx: usize| -> usize {
+            if read_idx <= write_idx {
+                len - write_idx + read_idx - 1
+            } else {
+                read_idx - write_idx - 1
+            }
+        };
+
+        let mut free = free_from(self.read_idx_cached_.get());
+        if free == 0 {
+            let read_idx = self.read_idx_.load(Ordering::Acquire);
+            self.read_idx_cached_.set(read_idx);
+            free = free_from(read_idx);
+        }
+        free
+    }
+
+    /// Occupied slots available to the consumer, refreshing the cached
+    /// write index from the shared atomic only once the cache looks dry.
+    fn filled_len(&self) -> usize {
+        let read_idx = self.read_idx_.load(Ordering::Relaxed);
+        let len = self.data_.len();
+
+        let filled_from = |write_idx: usize| -> usize {
+            if write_idx >= read_idx {
+                write_idx - read_idx
+            } else {
+                len - read_idx + write_idx
+            }
+        };
+
+        let mut filled = filled_from(self.write_idx_cached_.get());
+        if filled == 0 {
+            let write_idx = self.write_idx_.load(Ordering::Acquire);
+            self.write_idx_cached_.set(write_idx);
+            filled = filled_from(write_idx);
+        }
+        filled
+    }
+
+    /// The writable region as one contiguous run, stopping at the point
+    /// where the ring wraps back to index 0. Does not advance `write_idx_`;
+    /// pair with `commit_write` once the caller has filled some prefix.
+    ///
+    /// Callers must not hold on to more than one outstanding slice from this
+    /// method at a time; like the rest of the producer-side API, it assumes
+    /// a single caller driving the write side.
+    #[allow(clippy::mut_from_ref)]
+    fn writable_contiguous(&self) -> &mut [MaybeUninit<T>] {
+        let write_idx = self.write_idx_.load(Ordering::Relaxed);
+        let run = self.free_len().min(self.data_.len() - write_idx);
+
+        // SAFETY: [write_idx, write_idx + run) is free for the producer;
+        // `run` is bounded by read_idx_cached_, which never runs ahead of the
+        // consumer's read_idx_, so the consumer holds no slot in this run.
+        unsafe { std::slice::from_raw_parts_mut(self.data_[write_idx].get(), run) }
+    }
+
+    /// The readable region as one contiguous run, stopping at the point
+    /// where the ring wraps back to index 0. Does not advance `read_idx_`;
+    /// pair with `commit_read` once the caller has consumed some prefix.
+    fn readable_contiguous(&self) -> &[T] {
+        let read_idx = self.read_idx_.load(Ordering::Relaxed);
+        let run = self.filled_len().min(self.data_.len() - read_idx);
+
+        // SAFETY: [read_idx, read_idx + run) was published by the producer
+        // and not yet reclaimed, so it holds initialized `T`s.
+        unsafe { std::slice::from_raw_parts(self.data_[read_idx].get() as *const T, run) }
+    }
+
+    /// Advances `write_idx_` by `n`, publishing the first `n` slots of the
+    /// last `writable_contiguous` run to the consumer.
+    fn commit_write(&self, n: usize) {
+        let len = self.data_.len();
+        let mut next = self.write_idx_.load(Ordering::Relaxed) + n;
+        if next >= len {
+            next -= len;
+        }
+        self.write_idx_.store(next, Ordering::Release);
+    }
+
+    /// Advances `read_idx_` by `n`, returning the first `n` slots of the
+    /// last `readable_contiguous` run to the producer.
+    fn commit_read(&self, n: usize) {
+        let len = self.data_.len();
+        let mut next = self.read_idx_.load(Ordering::Relaxed) + n;
+        if next >= len {
+            next -= len;
+        }
+        self.read_idx_.store(next, Ordering::Release);
+    }
+
+    /// Bulk-copies as many elements of `src` as fit into one contiguous
+    /// writable run, a single `memcpy` instead of one `push` per element.
+    /// Returns the number of elements actually written.
+    fn push_slice(&self, src: &[T]) -> usize {
+        let dst = self.writable_contiguous();
+        let n = src.len().min(dst.len());
+
+        // SAFETY: `dst[..n]` is the free, producer-owned region returned by
+        // writable_contiguous; `commit_write` below hands it to the consumer.
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr() as *mut T, n);
+        }
+        self.commit_write(n);
+        n
+    }
+
+    /// Bulk-copies as many elements as fit out of one contiguous readable
+    /// run into `dst`, a single `memcpy` instead of one `pop` per element.
+    /// Returns the number of elements actually read.
+    fn pop_slice(&self, dst: &mut [T]) -> usize {
+        let src = self.readable_contiguous();
+        let n = dst.len().min(src.len());
+
+        // SAFETY: `src[..n]` is the initialized, consumer-owned region
+        // returned by readable_contiguous; `commit_read` below reclaims it.
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), n);
+        }
+        self.commit_read(n);
+        n
+    }
+}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        let mut read_idx = *self.read_idx_.get_mut();
+        let write_idx = *self.write_idx_.get_mut();
+
+        while read_idx != write_idx {
+            // SAFETY: every slot between read_idx and write_idx is logically
+            // occupied and has not been read out yet, so it holds a live `T`.
+            unsafe {
+                ptr::drop_in_place((*self.data_[read_idx].get()).as_mut_ptr());
+            }
+            read_idx += 1;
+            if read_idx == self.data_.len() {
+                read_idx = 0;
+            }
+        }
+    }
+}
+
+/// The producer side of a [`RingBuffer`] split, owning the write cursor.
+struct Producer<T> {
+    inner: Arc<RingBuffer<T>>,
+}
+
+/// The consumer side of a [`RingBuffer`] split, owning the read cursor.
+struct Consumer<T> {
+    inner: Arc<RingBuffer<T>>,
+}
+
+unsafe impl<T: Send> Send for Producer<T> {}
+unsafe impl<T: Send> Send for Consumer<T> {}
+
+impl<T> Producer<T> {
+    fn push(&self, val: T) -> Result<(), T> {
+        self.inner.push(val)
+    }
+
+    /// Returns a future that resolves once `val` has been pushed, registering
+    /// a waker on the buffer's producer-side slot while the buffer is full
+    /// and retrying each time `Consumer::pop` wakes it.
+    fn push_async(&self, val: T) -> PushFuture<'_, T> {
+        PushFuture {
+            producer: self,
+            val: Some(val),
+        }
+    }
+}
+
+impl<T: Copy> Producer<T> {
+    /// The writable region as one contiguous run; see
+    /// [`RingBuffer::writable_contiguous`].
+    #[allow(clippy::mut_from_ref)]
+    fn writable_contiguous(&self) -> &mut [MaybeUninit<T>] {
+        self.inner.writable_contiguous()
+    }
+
+    /// Advances the write cursor by `n`; see [`RingBuffer::commit_write`].
+    fn commit_write(&self, n: usize) {
+        self.inner.commit_write(n)
+    }
+
+    /// Bulk-copies as much of `src` as fits; see [`RingBuffer::push_slice`].
+    fn push_slice(&self, src: &[T]) -> usize {
+        self.inner.push_slice(src)
+    }
+}
+
+impl<T> Consumer<T> {
+    fn pop(&self) -> Option<T> {
+        self.inner.pop()
+    }
+
+    /// Returns a future that resolves to the next popped value, registering
+    /// a waker on the buffer's consumer-side slot while the buffer is empty
+    /// and retrying each time `Producer::push` wakes it.
+    fn pop_async(&self) -> PopFuture<'_, T> {
+        PopFuture { consumer: self }
+    }
+}
+
+impl<T: Copy> Consumer<T> {
+    /// The readable region as one contiguous run; see
+    /// [`RingBuffer::readable_contiguous`].
+    fn readable_contiguous(&self) -> &[T] {
+        self.inner.readable_contiguous()
+    }
+
+    /// Advances the read cursor by `n`; see [`RingBuffer::commit_read`].
+    fn commit_read(&self, n: usize) {
+        self.inner.commit_read(n)
+    }
+
+    /// Bulk-copies as much as fits into `dst`; see [`RingBuffer::pop_slice`].
+    fn pop_slice(&self, dst: &mut [T]) -> usize {
+        self.inner.pop_slice(dst)
+    }
+}
+
+/// Future returned by [`Producer::push_async`].
+struct PushFuture<'a, T> {
+    producer: &'a Producer<T>,
+    val: Option<T>,
+}
+
+// Neither field is address-sensitive, so the future can always be moved
+// freely regardless of whether `T` itself is `Unpin`.
+impl<T> Unpin for PushFuture<'_, T> {}
+
+impl<T> Future for PushFuture<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let val = this.val.take().expect("PushFuture polled after completion");
+
+        match this.producer.inner.push(val) {
+            Ok(()) => return Poll::Ready(()),
+            Err(val) => this.val = Some(val),
+        }
+
+        this.producer.inner.producer_waker_.register(cx.waker());
+
+        // The consumer may have freed a slot between the attempt above and
+        // registering the waker; retry once more before yielding.
+        let val = this.val.take().expect("val was just reinserted above");
+        match this.producer.inner.push(val) {
+            Ok(()) => Poll::Ready(()),
+            Err(val) => {
+                this.val = Some(val);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Future returned by [`Consumer::pop_async`].
+struct PopFuture<'a, T> {
+    consumer: &'a Consumer<T>,
+}
+
+// No address-sensitive data here either, for the same reason as `PushFuture`.
+impl<T> Unpin for PopFuture<'_, T> {}
+
+impl<T> Future for PopFuture<'_, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = self.get_mut();
+
+        if let Some(val) = this.consumer.inner.pop() {
+            return Poll::Ready(val);
+        }
+
+        this.consumer.inner.consumer_waker_.register(cx.waker());
+
+        // The producer may have pushed a value between the attempt above and
+        // registering the waker; retry once more before yielding.
+        match this.consumer.inner.pop() {
+            Some(val) => Poll::Ready(val),
+            None => Poll::Pending,
+        }
+    }
+}
+
+const SPIN_LIMIT: u32 = 6;
+const YIELD_LIMIT: u32 = 10;
+
+/// Exponential-backoff spinner used between failed CAS attempts on the
+/// [`AtomicQueue`] fast path: a few rounds of `spin_loop`, then a yield to
+/// the scheduler once contention looks sustained.
+struct Backoff {
+    step: Cell<u32>,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self { step: Cell::new(0) }
+    }
+
+    fn spin(&self) {
+        let step = self.step.get();
+        if step <= SPIN_LIMIT {
+            for _ in 0..(1 << step) {
+                std::hint::spin_loop();
+            }
+        } else {
+            std::thread::yield_now();
+        }
+
+        if step <= YIELD_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+}
+
+/// A single slot of an [`AtomicQueue`]: the value storage plus a stamp that
+/// encodes which producer/consumer counter value is allowed to touch it
+/// next, following the scheme used by crossbeam's array queue.
+struct Slot<T> {
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A bounded multi-producer/multi-consumer queue, as a sibling to the
+/// single-producer/single-consumer [`RingBuffer`]. Each slot carries its own
+/// stamp so concurrent producers (and concurrent consumers) race over a
+/// single CAS on a shared counter rather than needing a lock.
+struct AtomicQueue<T> {
+    buffer: Box<[Slot<T>]>,
+    capacity: usize,
+    one_lap: usize,
+    head: Aligned<A64, AtomicUsize>,
+    tail: Aligned<A64, AtomicUsize>,
+}
+
+unsafe impl<T: Send> Send for AtomicQueue<T> {}
+unsafe impl<T: Send> Sync for AtomicQueue<T> {}
+
+impl<T> AtomicQueue<T> {
+    fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+
+        let buffer = (0..capacity)
+            .map(|i| Slot {
+                stamp: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+
+        Self {
+            buffer,
+            capacity,
+            one_lap: capacity,
+            head: Aligned(AtomicUsize::new(0)),
+            tail: Aligned(AtomicUsize::new(0)),
+        }
+    }
+
+    fn index(&self, counter: usize) -> usize {
+        if self.capacity.is_power_of_two() {
+            counter & (self.capacity - 1)
+        } else {
+            counter % self.capacity
+        }
+    }
+
+    fn push(&self, val: T) -> Result<(), T> {
+        let backoff = Backoff::new();
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.buffer[self.index(tail)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == tail {
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    tail + 1,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: winning the CAS above gives us exclusive
+                        // ownership of this slot until its stamp is
+                        // published below.
+                        unsafe {
+                            (*slot.value.get()).write(val);
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(t) => {
+                        tail = t;
+                        backoff.spin();
+                    }
+                }
+            } else if stamp < tail {
+                return Err(val);
+            } else {
+                backoff.spin();
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<T> {
+        let backoff = Backoff::new();
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.buffer[self.index(head)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == head + 1 {
+                match self.head.compare_exchange_weak(
+                    head,
+                    head + 1,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: winning the CAS above gives us exclusive
+                        // ownership of this slot until its stamp is
+                        // published below.
+                        let val = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.stamp.store(head + self.one_lap, Ordering::Release);
+                        return Some(val);
+                    }
+                    Err(h) => {
+                        head = h;
+                        backoff.spin();
+                    }
+                }
+            } else if stamp == head {
+                return None;
+            } else {
+                backoff.spin();
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T> Drop for AtomicQueue<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+
+        let mut cur = head;
+        while cur != tail {
+            let idx = self.index(cur);
+            // SAFETY: every counter value between head and tail denotes a
+            // slot that was written by `push` and not yet read by `pop`.
+            unsafe {
+                ptr::drop_in_place(self.buffer[idx].value.get_mut().as_mut_ptr());
+            }
+            cur += 1;
+        }
+    }
+}
+
+/// Errors surfaced by [`SharedRingBuffer`] when the peer's half of a shared
+/// memory region looks corrupted rather than merely contended.
+#[derive(Debug, PartialEq, Eq)]
+enum RingError {
+    /// A counter loaded from the peer's side of the shared region either
+    /// outran what the peer could legitimately have reached, or regressed
+    /// behind a value we had already trusted, and cannot be a legitimate
+    /// counter.
+    MalformedIndex,
+}
+
+impl std::fmt::Display for RingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RingError::MalformedIndex => {
+                write!(f, "peer reported a counter outside the valid range")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RingError {}
+
+/// The fixed-size portion of a [`SharedRingBuffer`] that lives at the start
+/// of the shared region, ahead of the slot data, so that both sides can
+/// locate it by the same offset.
+///
+/// Both counters are ever-increasing (never wrapped to `0..capacity`), the
+/// same scheme [`AtomicQueue`] uses internally: the slot index is derived
+/// with `counter % capacity`. Unlike a wrapped index, a raw counter lets a
+/// peer's reported progress be checked for monotonicity unambiguously —
+/// with a wrapped index, "advanced by one lap" and "rewound" are the same
+/// bit pattern.
+#[repr(C)]
+struct SharedHeader {
+    read_idx: AtomicUsize,
+    write_idx: AtomicUsize,
+}
+
+/// A ring buffer laid out over a caller-supplied, pre-mapped memory region
+/// (e.g. POSIX shared memory) instead of an owned allocation, so two
+/// separate processes can each build one over the same bytes and exchange
+/// values as an IPC transport.
+///
+/// Unlike [`RingBuffer`], the peer's counters are untrusted input: every
+/// counter loaded from the shared header must be both in-range and a
+/// forward move from the last value we saw, or it is rejected as
+/// [`RingError::MalformedIndex`] instead of causing an out-of-bounds access,
+/// a desynchronized ring, or an infinite retry loop. The shared header is
+/// writable by the peer on both of its fields, so even the field this side
+/// nominally "owns" is not trustworthy as a read source: each side keeps its
+/// own cursor in a local `Cell` and only ever stores into the header to
+/// publish it, never loads it back.
+struct SharedRingBuffer<T> {
+    header: *const SharedHeader,
+    data: *const UnsafeCell<MaybeUninit<T>>,
+    capacity: usize,
+    /// This side's own write cursor. The shared header is writable by the
+    /// peer too, so it cannot be trusted to hold our own progress; this
+    /// `Cell` is the only value ever read back as "ours", and the header
+    /// field of the same name is written to but never read by `push`.
+    own_write_idx_: Cell<usize>,
+    /// This side's own read cursor, with the same trust split as
+    /// `own_write_idx_`.
+    own_read_idx_: Cell<usize>,
+    /// Last validated value seen from the peer's read_idx, used both to
+    /// avoid re-validating on every `push` and as the floor a new remote
+    /// value must not regress below.
+    peer_read_idx_cached_: Cell<usize>,
+    /// Last validated value seen from the peer's write_idx; the `pop`
+    /// counterpart of `peer_read_idx_cached_`.
+    peer_write_idx_cached_: Cell<usize>,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for SharedRingBuffer<T> {}
+unsafe impl<T: Send> Sync for SharedRingBuffer<T> {}
+
+impl<T> SharedRingBuffer<T> {
+    /// Bytes the caller must reserve for a shared region of this capacity:
+    /// the header followed by `capacity` slots.
+    fn region_size(capacity: usize) -> usize {
+        std::mem::size_of::<SharedHeader>() + capacity * std::mem::size_of::<MaybeUninit<T>>()
+    }
+
+    /// Allocates an owned, zero-initialized region of `Self::region_size(capacity)`
+    /// bytes, suitable for `init_region`/`from_shared`. Backed by `u64`
+    /// elements so the start address is aligned for `SharedHeader` (whose
+    /// `AtomicUsize` fields need 8-byte alignment); a plain `Vec<u8>` is only
+    /// guaranteed 1-byte alignment and would make `from_shared` undefined
+    /// behavior.
+    fn alloc_region(capacity: usize) -> Vec<u64> {
+        let bytes = Self::region_size(capacity);
+        let words = bytes.div_ceil(std::mem::size_of::<u64>());
+        vec![0u64; words]
+    }
+
+    /// Zero-initializes the header of a freshly mapped region. Exactly one
+    /// side of the connection must call this, before either side calls
+    /// `from_shared` and starts pushing or popping.
+    ///
+    /// # Safety
+    /// `ptr` must point to at least `Self::region_size(capacity)` writable
+    /// bytes, valid for the `'static` lifetime of the mapping, and must not
+    /// already be in use by a live `SharedRingBuffer`.
+    unsafe fn init_region(ptr: *mut u8) {
+        ptr::write(
+            ptr as *mut SharedHeader,
+            SharedHeader {
+                read_idx: AtomicUsize::new(0),
+                write_idx: AtomicUsize::new(0),
+            },
+        );
+    }
+
+    /// Builds a handle over an externally provided, pre-mapped memory
+    /// region. Both processes sharing the region call this with their own
+    /// (equally mapped) `ptr` after `init_region` has run once.
+    ///
+    /// # Safety
+    /// `ptr` must point to at least `Self::region_size(capacity)` bytes,
+    /// suitably aligned for `SharedHeader` and `T`, that outlive this
+    /// handle and have already been initialized by `init_region`.
+    unsafe fn from_shared(ptr: *mut u8, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+
+        let header = ptr as *const SharedHeader;
+        let data = ptr.add(std::mem::size_of::<SharedHeader>()) as *const UnsafeCell<MaybeUninit<T>>;
+
+        Self {
+            header,
+            data,
+            capacity,
+            own_write_idx_: Cell::new(0),
+            own_read_idx_: Cell::new(0),
+            peer_read_idx_cached_: Cell::new(0),
+            peer_write_idx_cached_: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    fn index(&self, counter: usize) -> usize {
+        if self.capacity.is_power_of_two() {
+            counter & (self.capacity - 1)
+        } else {
+            counter % self.capacity
+        }
+    }
+
+    /// Validates a counter loaded from the peer's side of the header. The
+    /// peer's memory is untrusted, so `candidate` is accepted only if it is
+    /// a forward (non-regressing) move from `cached` — the last value we
+    /// trusted — and does not overrun `max`, the most progress the peer
+    /// could legitimately have made (bounded by our own counter). Anything
+    /// else — a rewind, a jump past `max`, or both — is corruption rather
+    /// than contention.
+    fn validate_remote_index(
+        &self,
+        cached: usize,
+        candidate: usize,
+        max: usize,
+    ) -> Result<usize, RingError> {
+        if candidate < cached || candidate > max {
+            return Err(RingError::MalformedIndex);
+        }
+        Ok(candidate)
+    }
+
+    fn push(&self, val: T) -> Result<bool, RingError> {
+        // SAFETY: `header` was built from a region that outlives `self`.
+        let header = unsafe { &*self.header };
+        let write_idx = self.own_write_idx_.get();
+
+        if write_idx - self.peer_read_idx_cached_.get() == self.capacity {
+            let cached = self.peer_read_idx_cached_.get();
+            let remote = header.read_idx.load(Ordering::Acquire);
+            let remote = self.validate_remote_index(cached, remote, write_idx)?;
+            self.peer_read_idx_cached_.set(remote);
+
+            if write_idx - remote == self.capacity {
+                return Ok(false);
+            }
+        }
+
+        // SAFETY: write_idx comes from our own cached cursor, not the
+        // shared header, so `index(write_idx)` is always `< capacity`; the
+        // peer has not consumed this slot yet, so we have exclusive access.
+        unsafe {
+            (*(*self.data.add(self.index(write_idx))).get()).write(val);
+        }
+        let next_write_idx = write_idx + 1;
+        self.own_write_idx_.set(next_write_idx);
+        header.write_idx.store(next_write_idx, Ordering::Release);
+
+        Ok(true)
+    }
+
+    fn pop(&self) -> Result<Option<T>, RingError> {
+        // SAFETY: `header` was built from a region that outlives `self`.
+        let header = unsafe { &*self.header };
+        let read_idx = self.own_read_idx_.get();
+
+        if read_idx == self.peer_write_idx_cached_.get() {
+            let cached = self.peer_write_idx_cached_.get();
+            let remote = header.write_idx.load(Ordering::Acquire);
+            let remote = self.validate_remote_index(cached, remote, read_idx + self.capacity)?;
+            self.peer_write_idx_cached_.set(remote);
+
+            if read_idx == remote {
+                return Ok(None);
+            }
+        }
+
+        // SAFETY: read_idx comes from our own cached cursor, not the shared
+        // header, so `index(read_idx)` is always `< capacity`; the peer has
+        // already published this slot and will not touch it again until we
+        // advance past it below.
+        let val = unsafe { (*(*self.data.add(self.index(read_idx))).get()).assume_init_read() };
+
+        let next_read_idx = read_idx + 1;
+        self.own_read_idx_.set(next_read_idx);
+        header.read_idx.store(next_read_idx, Ordering::Release);
+
+        Ok(Some(val))
     }
 }
 
@@ -90,16 +885,16 @@ fn pin_thread(cpu: usize) {
     }
 }
 
-fn bench(cpu1: usize, cpu2: usize, iters: i32, buffer_rign: RingBuffer) {
-    let q = Arc::new(Mutex::new(buffer_rign));
-    let q2 = Arc::clone(&q);
+fn bench(cpu1: usize, cpu2: usize, iters: i32, buffer_rign: RingBuffer<i32>) {
+    let (producer, consumer) = buffer_rign.split();
 
     let t = std::thread::spawn(move || {
         pin_thread(cpu1);
         for i in 0..iters {
-            let mut val = 0_i32;
+            let val;
             loop {
-                if q.lock().unwrap().pop(&mut val) {
+                if let Some(v) = consumer.pop() {
+                    val = v;
                     break;
                 }
             }
@@ -116,21 +911,14 @@ fn bench(cpu1: usize, cpu2: usize, iters: i32, buffer_rign: RingBuffer) {
 
     for i in 0..iters {
         loop {
-            if q2.lock().unwrap().push(i) {
+            if producer.push(i).is_ok() {
                 break;
             }
         }
     }
 
-    loop {
-        let q = q2.lock().unwrap();
-        if q.read_idx_.load(Ordering::Relaxed) == q.write_idx_.load(Ordering::Relaxed) {
-            break;
-        }
-    }
-
-    let stop = start.elapsed();
     t.join().unwrap();
+    let stop = start.elapsed();
 
     let secs = stop.as_secs() as f64 + f64::from(stop.subsec_nanos()) * 1e-9;
     println!(
@@ -141,7 +929,166 @@ fn bench(cpu1: usize, cpu2: usize, iters: i32, buffer_rign: RingBuffer) {
     );
 }
 
+/// Demonstrates the MPMC [`AtomicQueue`] with several producer threads
+/// racing a single consumer over the stamped-slot CAS path.
+fn atomic_queue_demo() {
+    let queue = Arc::new(AtomicQueue::<i32>::new(64));
+    let producers = 4;
+    let per_producer = 1_000;
+
+    let handles: Vec<_> = (0..producers)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            std::thread::spawn(move || {
+                for i in 0..per_producer {
+                    while queue.push(i).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let total = producers * per_producer;
+    let mut received = 0;
+    while received < total {
+        if queue.pop().is_some() {
+            received += 1;
+        }
+    }
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    println!(
+        "AtomicQueue: {} producers x {} items, {} received",
+        producers, per_producer, received
+    );
+}
+
+/// Demonstrates the shared-memory IPC transport: two handles built over the
+/// same region, as if constructed by two separate processes mapping the
+/// same bytes.
+fn shared_ring_buffer_demo() {
+    let capacity = 8;
+    let mut region = SharedRingBuffer::<i32>::alloc_region(capacity);
+    let ptr = region.as_mut_ptr() as *mut u8;
+
+    // SAFETY: `region` is sized and aligned by `alloc_region` for
+    // `SharedRingBuffer::<i32>`'s header and `capacity` slots, and outlives
+    // both handles below.
+    unsafe { SharedRingBuffer::<i32>::init_region(ptr) };
+    let producer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+    let consumer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+
+    for i in 0..20 {
+        while !producer.push(i).unwrap() {
+            std::thread::yield_now();
+        }
+        loop {
+            if let Some(v) = consumer.pop().unwrap() {
+                assert_eq!(v, i);
+                break;
+            }
+        }
+    }
+
+    println!("SharedRingBuffer: round-tripped 20 values over a shared region");
+}
+
+/// Demonstrates the zero-copy bulk slice API through a split
+/// [`Producer`]/[`Consumer`] pair, both the `push_slice`/`pop_slice`
+/// convenience and the lower-level contiguous-run accessors they build on.
+fn bulk_slice_demo() {
+    let (producer, consumer) = RingBuffer::<u8>::new(64).split();
+
+    let writable = producer.writable_contiguous();
+    let seed_len = 4.min(writable.len());
+    for (slot, val) in writable.iter_mut().zip(0u8..).take(seed_len) {
+        slot.write(val);
+    }
+    producer.commit_write(seed_len);
+
+    let readable = consumer.readable_contiguous();
+    let seeded: Vec<u8> = readable[..seed_len].to_vec();
+    consumer.commit_read(seed_len);
+
+    let src: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let mut dst = seeded;
+    let mut written = 0;
+
+    while dst.len() < seed_len + src.len() {
+        written += producer.push_slice(&src[written..]);
+
+        let mut chunk = [0u8; 64];
+        let n = consumer.pop_slice(&mut chunk);
+        dst.extend_from_slice(&chunk[..n]);
+    }
+
+    let expected: Vec<u8> = (0..seed_len as u8).chain(src.iter().copied()).collect();
+    assert_eq!(dst, expected);
+    println!(
+        "RingBuffer bulk slice: moved {} bytes via writable_contiguous/push_slice/pop_slice",
+        dst.len()
+    );
+}
+
+/// Wakes the thread blocked in [`block_on`] once the polled future makes
+/// progress, the minimal executor a `push_async`/`pop_async` caller needs.
+struct ParkWaker(std::thread::Thread);
+
+impl Wake for ParkWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drives a `Future` to completion on the current thread by parking between
+/// polls, just enough of an executor to demonstrate `push_async`/`pop_async`
+/// outside of a real async runtime.
+fn block_on<F: Future + Unpin>(mut fut: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ParkWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match Pin::new(&mut fut).poll(&mut cx) {
+            Poll::Ready(val) => return val,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Demonstrates `push_async`/`pop_async` backing a tiny async channel: a
+/// background thread polls each `pop_async` to completion while the main
+/// thread drives `push_async`.
+fn async_demo() {
+    let (producer, consumer) = RingBuffer::<i32>::new(4).split();
+
+    let reader = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        for _ in 0..10 {
+            received.push(block_on(consumer.pop_async()));
+        }
+        received
+    });
+
+    for i in 0..10 {
+        block_on(producer.push_async(i));
+    }
+
+    let received = reader.join().unwrap();
+    println!(
+        "Async channel: received {:?} via push_async/pop_async",
+        received
+    );
+}
+
 fn main() {
+    atomic_queue_demo();
+    bulk_slice_demo();
+    shared_ring_buffer_demo();
+    async_demo();
+
     let queue = 100000;
     let cpu1 = 0;
     let cpu2 = 1;
@@ -149,3 +1096,274 @@ fn main() {
     bench(cpu1, cpu2, iters, RingBuffer::new(queue));
     println!("Done");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn ring_buffer_fill_empty_and_wrap_around() {
+        let rb = RingBuffer::<i32>::new(4);
+        assert_eq!(rb.push(1), Ok(()));
+        assert_eq!(rb.push(2), Ok(()));
+        assert_eq!(rb.push(3), Ok(()));
+        assert_eq!(rb.push(4), Err(4));
+
+        assert_eq!(rb.pop(), Some(1));
+        assert_eq!(rb.pop(), Some(2));
+
+        for v in [4, 5] {
+            assert_eq!(rb.push(v), Ok(()));
+        }
+        assert_eq!(rb.push(6), Err(6));
+
+        assert_eq!(rb.pop(), Some(3));
+        assert_eq!(rb.pop(), Some(4));
+        assert_eq!(rb.pop(), Some(5));
+        assert_eq!(rb.pop(), None);
+    }
+
+    #[test]
+    fn ring_buffer_drops_unread_values() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let rb = RingBuffer::<Rc<()>>::new(4);
+        rb.push(Rc::clone(&counter)).unwrap();
+        rb.push(Rc::clone(&counter)).unwrap();
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        rb.pop().unwrap();
+        assert_eq!(Rc::strong_count(&counter), 2);
+
+        drop(rb);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn producer_consumer_spsc_across_threads() {
+        let (producer, consumer) = RingBuffer::<i32>::new(16).split();
+        let n = 10_000;
+
+        let reader = std::thread::spawn(move || {
+            let mut received = Vec::with_capacity(n);
+            while received.len() < n {
+                if let Some(v) = consumer.pop() {
+                    received.push(v);
+                }
+            }
+            received
+        });
+
+        for i in 0..n as i32 {
+            while producer.push(i).is_err() {}
+        }
+
+        assert_eq!(reader.join().unwrap(), (0..n as i32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn producer_consumer_bulk_slice_round_trip() {
+        let (producer, consumer) = RingBuffer::<u8>::new(8).split();
+
+        let src = [1u8, 2, 3, 4, 5];
+        assert_eq!(producer.push_slice(&src), 5);
+
+        let mut dst = [0u8; 5];
+        assert_eq!(consumer.pop_slice(&mut dst), 5);
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn producer_consumer_contiguous_accessors() {
+        let (producer, consumer) = RingBuffer::<u8>::new(8).split();
+
+        let writable = producer.writable_contiguous();
+        let n = 3.min(writable.len());
+        for (slot, val) in writable.iter_mut().zip([10u8, 20, 30]).take(n) {
+            slot.write(val);
+        }
+        producer.commit_write(n);
+
+        let readable = consumer.readable_contiguous();
+        assert_eq!(&readable[..n], &[10, 20, 30][..n]);
+        consumer.commit_read(n);
+        assert!(consumer.readable_contiguous().is_empty());
+    }
+
+    #[test]
+    fn atomic_queue_fill_and_empty() {
+        let q = AtomicQueue::<i32>::new(2);
+        assert_eq!(q.push(1), Ok(()));
+        assert_eq!(q.push(2), Ok(()));
+        assert_eq!(q.push(3), Err(3));
+
+        assert_eq!(q.pop(), Some(1));
+        assert_eq!(q.pop(), Some(2));
+        assert_eq!(q.pop(), None);
+    }
+
+    #[test]
+    fn atomic_queue_mpmc_across_threads() {
+        let q = Arc::new(AtomicQueue::<i32>::new(64));
+        let per_producer = 2_000;
+        let producers = 4;
+
+        let handles: Vec<_> = (0..producers)
+            .map(|_| {
+                let q = Arc::clone(&q);
+                std::thread::spawn(move || {
+                    for i in 0..per_producer {
+                        while q.push(i).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let total = producers * per_producer;
+        let consumer = std::thread::spawn(move || {
+            let mut count = 0;
+            while count < total {
+                if q.pop().is_some() {
+                    count += 1;
+                }
+            }
+            count
+        });
+
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(consumer.join().unwrap(), total);
+    }
+
+    fn shared_region<T>(capacity: usize) -> Vec<u64> {
+        SharedRingBuffer::<T>::alloc_region(capacity)
+    }
+
+    #[test]
+    fn shared_ring_buffer_round_trip() {
+        let capacity = 4;
+        let mut region = shared_region::<i32>(capacity);
+        let ptr = region.as_mut_ptr() as *mut u8;
+        unsafe { SharedRingBuffer::<i32>::init_region(ptr) };
+
+        let producer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+        let consumer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+
+        for i in 0..capacity as i32 {
+            assert_eq!(producer.push(i), Ok(true));
+        }
+        assert_eq!(producer.push(99), Ok(false));
+
+        for i in 0..capacity as i32 {
+            assert_eq!(consumer.pop(), Ok(Some(i)));
+        }
+        assert_eq!(consumer.pop(), Ok(None));
+    }
+
+    #[test]
+    fn shared_ring_buffer_rejects_out_of_range_peer_index() {
+        let capacity = 4;
+        let mut region = shared_region::<i32>(capacity);
+        let ptr = region.as_mut_ptr() as *mut u8;
+        unsafe { SharedRingBuffer::<i32>::init_region(ptr) };
+
+        let producer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+        for i in 0..capacity as i32 {
+            producer.push(i).unwrap();
+        }
+
+        // SAFETY: region is large enough for the header laid out by init_region.
+        let header = unsafe { &*(ptr as *const SharedHeader) };
+        header
+            .read_idx
+            .store(usize::MAX, Ordering::Release);
+
+        assert_eq!(producer.push(100), Err(RingError::MalformedIndex));
+    }
+
+    #[test]
+    fn shared_ring_buffer_rejects_rewound_peer_index() {
+        let capacity = 4;
+        let mut region = shared_region::<i32>(capacity);
+        let ptr = region.as_mut_ptr() as *mut u8;
+        unsafe { SharedRingBuffer::<i32>::init_region(ptr) };
+
+        let producer = unsafe { SharedRingBuffer::<i32>::from_shared(ptr, capacity) };
+        for i in 0..capacity as i32 {
+            producer.push(i).unwrap();
+        }
+        assert_eq!(producer.push(99), Ok(false));
+
+        // SAFETY: region is large enough for the header laid out by init_region.
+        let header = unsafe { &*(ptr as *const SharedHeader) };
+        // Consumer legitimately advances read_idx to 2, which the producer
+        // picks up and uses to free two more slots.
+        header.read_idx.store(2, Ordering::Release);
+        assert_eq!(producer.push(100), Ok(true));
+        assert_eq!(producer.push(101), Ok(true));
+
+        // Now a corrupt peer rewinds read_idx behind the value we already
+        // trusted; the next push that needs to refresh its cache must
+        // reject it instead of desynchronizing the ring.
+        header.read_idx.store(1, Ordering::Release);
+        assert_eq!(producer.push(102), Err(RingError::MalformedIndex));
+    }
+
+    struct FlagWaker(AtomicBool);
+
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Release);
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    #[test]
+    fn push_async_wakes_waiting_producer_on_pop() {
+        let (producer, consumer) = RingBuffer::<i32>::new(2).split();
+        producer.push(1).unwrap();
+
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = producer.push_async(2);
+        assert_eq!(
+            Pin::new(&mut fut).poll(&mut cx),
+            Poll::Pending,
+            "a capacity-2 buffer holds one value, so it is full after the first push"
+        );
+        assert!(!flag.0.load(Ordering::Acquire));
+
+        assert_eq!(consumer.pop(), Some(1));
+        assert!(flag.0.load(Ordering::Acquire), "pop should wake the producer");
+
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn pop_async_wakes_waiting_consumer_on_push() {
+        let (producer, consumer) = RingBuffer::<i32>::new(2).split();
+
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = consumer.pop_async();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        assert!(!flag.0.load(Ordering::Acquire));
+
+        producer.push(7).unwrap();
+        assert!(flag.0.load(Ordering::Acquire), "push should wake the consumer");
+
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(7));
+    }
+}